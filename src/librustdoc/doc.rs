@@ -15,6 +15,15 @@ use doc;
 
 pub type AstId = int;
 
+/**
+ * The version of the serialized `Doc` model (see `json_writer`).
+ *
+ * Bump this whenever the shape of `Doc`, `ItemDoc` or any `ItemTag`
+ * variant changes, so that downstream tools reading the JSON output can
+ * detect an incompatible schema.
+ */
+pub static FORMAT_VERSION: int = 1;
+
 #[deriving(Eq)]
 pub struct Doc {
     pages: ~[Page]
@@ -60,7 +69,23 @@ pub enum ItemTag {
     TraitTag(TraitDoc),
     ImplTag(ImplDoc),
     TyTag(TyDoc),
-    StructTag(StructDoc)
+    StructTag(StructDoc),
+    // A node that a filtering pass (strip-private, strip-hidden, ...) has
+    // removed from the tree but wants to keep a stub of, so that a later
+    // rendering pass can emit a redirect to wherever the item is still
+    // reachable. It preserves the original node's `item()` but exposes no
+    // children, keeping the tree shape pass-agnostic.
+    StrippedTag(~ItemTag)
+}
+
+/// The visibility of an item as declared in the source.
+#[deriving(Eq)]
+pub enum Visibility {
+    Public,
+    Private,
+    // A `pub(in path)` restriction, holding the module path it is visible
+    // within (`pub(crate)` being the crate root path).
+    Restricted(~[~str])
 }
 
 #[deriving(Eq)]
@@ -72,13 +97,35 @@ pub struct ItemDoc {
     desc: Option<~str>,
     sections: ~[Section],
     // Indicates that this node is a reexport of a different item
-    reexport: bool
+    reexport: bool,
+    visibility: Visibility
+}
+
+/// A single type parameter and the trait/lifetime bounds written on it.
+#[deriving(Eq)]
+pub struct TyParam {
+    name: ~str,
+    bounds: ~[~str]
+}
+
+/**
+ * The generic parameters of an item.
+ *
+ * Keeping lifetimes and type parameters structured (rather than baked into
+ * `sig`) lets the rendering passes reconstruct `fn foo<'a, T: Clone>(...)`
+ * and hyperlink each bound trait instead of re-parsing a prebuilt string.
+ */
+#[deriving(Eq)]
+pub struct Generics {
+    lifetimes: ~[~str],
+    type_params: ~[TyParam]
 }
 
 #[deriving(Eq)]
 pub struct SimpleItemDoc {
     item: ItemDoc,
-    sig: Option<~str>
+    sig: Option<~str>,
+    generics: Option<Generics>
 }
 
 #[deriving(Eq)]
@@ -102,7 +149,8 @@ pub type FnDoc = SimpleItemDoc;
 #[deriving(Eq)]
 pub struct EnumDoc {
     item: ItemDoc,
-    variants: ~[VariantDoc]
+    variants: ~[VariantDoc],
+    generics: Option<Generics>
 }
 
 #[deriving(Eq)]
@@ -115,7 +163,8 @@ pub struct VariantDoc {
 #[deriving(Eq)]
 pub struct TraitDoc {
     item: ItemDoc,
-    methods: ~[MethodDoc]
+    methods: ~[MethodDoc],
+    generics: Option<Generics>
 }
 
 #[deriving(Eq)]
@@ -131,10 +180,12 @@ pub struct MethodDoc {
 #[deriving(Eq)]
 pub struct ImplDoc {
     item: ItemDoc,
-    bounds_str: Option<~str>,
+    // Structured where-clause bounds, replacing the old `bounds_str`.
+    where_bounds: ~[TyParam],
     trait_types: ~[~str],
     self_ty: Option<~str>,
-    methods: ~[MethodDoc]
+    methods: ~[MethodDoc],
+    generics: Option<Generics>
 }
 
 pub type TyDoc = SimpleItemDoc;
@@ -143,7 +194,84 @@ pub type TyDoc = SimpleItemDoc;
 pub struct StructDoc {
     item: ItemDoc,
     fields: ~[~str],
-    sig: Option<~str>
+    sig: Option<~str>,
+    generics: Option<Generics>
+}
+
+/**
+ * The kind of item an index entry or cross-link points at.
+ *
+ * Having a dedicated type instead of a free-form string means every
+ * producer and consumer agrees on the set of kinds, and the canonical
+ * short name and link prefix live in one place (see `short_name` and
+ * `prefix`) rather than being re-derived from strings in each backend.
+ */
+#[deriving(Eq)]
+pub enum ItemKind {
+    Module,
+    ForeignMod,
+    Const,
+    Fn,
+    Enum,
+    Trait,
+    Impl,
+    Ty,
+    Struct,
+    Variant,
+    Method
+}
+
+impl ItemKind {
+    /// The human-readable name of this kind, e.g. as used in index headings.
+    pub fn short_name(&self) -> ~str {
+        match *self {
+          Module => ~"Module",
+          ForeignMod => ~"Foreign module",
+          Const => ~"Const",
+          Fn => ~"Function",
+          Enum => ~"Enum",
+          Trait => ~"Trait",
+          Impl => ~"Implementation",
+          Ty => ~"Type",
+          Struct => ~"Struct",
+          Variant => ~"Variant",
+          Method => ~"Method"
+        }
+    }
+
+    /// The URL-fragment prefix a backend prepends when anchoring a link to
+    /// an item of this kind.
+    pub fn prefix(&self) -> ~str {
+        match *self {
+          Module => ~"mod",
+          ForeignMod => ~"foreignmod",
+          Const => ~"const",
+          Fn => ~"fn",
+          Enum => ~"enum",
+          Trait => ~"trait",
+          Impl => ~"impl",
+          Ty => ~"ty",
+          Struct => ~"struct",
+          Variant => ~"variant",
+          Method => ~"method"
+        }
+    }
+}
+
+/// The `ItemKind` corresponding to a given item node.
+pub fn item_kind(tag: &ItemTag) -> ItemKind {
+    match *tag {
+      ModTag(*) => Module,
+      NmodTag(*) => ForeignMod,
+      ConstTag(*) => Const,
+      FnTag(*) => Fn,
+      EnumTag(*) => Enum,
+      TraitTag(*) => Trait,
+      ImplTag(*) => Impl,
+      TyTag(*) => Ty,
+      StructTag(*) => Struct,
+      StrippedTag(ref inner) => item_kind(&**inner)
+    }
 }
 
 #[deriving(Eq)]
@@ -156,14 +284,14 @@ pub struct Index {
  *
  * Fields:
  *
- * * kind - The type of thing being indexed, e.g. 'Module'
+ * * kind - The kind of thing being indexed, e.g. `Module`
  * * name - The name of the thing
  * * brief - The brief description
  * * link - A format-specific string representing the link target
  */
 #[deriving(Eq)]
 pub struct IndexEntry {
-    kind: ~str,
+    kind: ItemKind,
     name: ~str,
     brief: Option<~str>,
     link: ~str
@@ -306,11 +434,23 @@ impl Item for ItemTag {
           &doc::TraitTag(ref doc) => copy doc.item,
           &doc::ImplTag(ref doc) => copy doc.item,
           &doc::TyTag(ref doc) => copy doc.item,
-          &doc::StructTag(ref doc) => copy doc.item
+          &doc::StructTag(ref doc) => copy doc.item,
+          &doc::StrippedTag(ref inner) => inner.item()
         }
     }
 }
 
+impl ItemTag {
+    /// Replace this node with a `StrippedTag` stub. The original node is
+    /// preserved so `item()` still answers with its `id`, `name` and
+    /// `path`, but because the stub is skipped by the `ModDoc` and
+    /// `PageUtils` accessors it contributes no children to index generation
+    /// or cross-reference resolution.
+    pub fn strip(self) -> ItemTag {
+        doc::StrippedTag(~self)
+    }
+}
+
 impl Item for SimpleItemDoc {
     fn item(&self) -> ItemDoc { copy self.item }
 }
@@ -346,6 +486,7 @@ pub trait ItemUtils {
     fn brief(&self) -> Option<~str>;
     fn desc(&self) -> Option<~str>;
     fn sections(&self) -> ~[Section];
+    fn visibility(&self) -> Visibility;
 }
 
 impl<A:Item> ItemUtils for A {
@@ -372,4 +513,8 @@ impl<A:Item> ItemUtils for A {
     fn sections(&self) -> ~[Section] {
         copy self.item().sections
     }
+
+    fn visibility(&self) -> Visibility {
+        copy self.item().visibility
+    }
 }
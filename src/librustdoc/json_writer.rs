@@ -0,0 +1,234 @@
+// Copyright 2012 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Serialize the document model to a stable, versioned JSON form
+//!
+//! This is an alternative backend to the markdown and HTML writers: rather
+//! than rendering prose it emits the whole `Doc` tree as JSON so that
+//! external tools can consume the extracted docs without scraping rendered
+//! output. The root object carries `doc::FORMAT_VERSION` under `"version"`;
+//! each item serializes to an object tagged by its `ItemKind`.
+
+use doc;
+use doc::ItemUtils;
+
+use extra::json;
+use extra::treemap::TreeMap;
+
+fn str_or_null(s: &Option<~str>) -> json::Json {
+    match *s {
+      Some(ref s) => json::String(copy *s),
+      None => json::Null
+    }
+}
+
+fn strs(ss: &[~str]) -> json::Json {
+    json::List(ss.iter().map(|s| json::String(copy *s)).collect())
+}
+
+fn visibility(vis: &doc::Visibility) -> json::Json {
+    match *vis {
+      doc::Public => json::String(~"public"),
+      doc::Private => json::String(~"private"),
+      doc::Restricted(ref path) => {
+        let mut obj = ~TreeMap::new();
+        obj.insert(~"restricted", strs(*path));
+        json::Object(obj)
+      }
+    }
+}
+
+fn ty_param(p: &doc::TyParam) -> json::Json {
+    let mut obj = ~TreeMap::new();
+    obj.insert(~"name", json::String(copy p.name));
+    obj.insert(~"bounds", strs(p.bounds));
+    json::Object(obj)
+}
+
+fn generics(g: &Option<doc::Generics>) -> json::Json {
+    match *g {
+      None => json::Null,
+      Some(ref g) => {
+        let mut obj = ~TreeMap::new();
+        obj.insert(~"lifetimes", strs(g.lifetimes));
+        obj.insert(~"type_params",
+                   json::List(g.type_params.iter().map(ty_param).collect()));
+        json::Object(obj)
+      }
+    }
+}
+
+fn section(s: &doc::Section) -> json::Json {
+    let mut obj = ~TreeMap::new();
+    obj.insert(~"header", json::String(copy s.header));
+    obj.insert(~"body", json::String(copy s.body));
+    json::Object(obj)
+}
+
+// Start an object with the fields shared by every item, tagged by kind.
+fn item_object(tag: &doc::ItemTag) -> ~TreeMap<~str, json::Json> {
+    let item = tag.item();
+    let mut obj = ~TreeMap::new();
+    obj.insert(~"kind", json::String(doc::item_kind(tag).prefix()));
+    obj.insert(~"id", json::Number(item.id as f64));
+    obj.insert(~"name", json::String(copy item.name));
+    obj.insert(~"path", strs(item.path));
+    obj.insert(~"brief", str_or_null(&item.brief));
+    obj.insert(~"desc", str_or_null(&item.desc));
+    obj.insert(~"sections",
+               json::List(item.sections.iter().map(section).collect()));
+    obj.insert(~"reexport", json::Boolean(item.reexport));
+    obj.insert(~"visibility", visibility(&item.visibility));
+    obj
+}
+
+fn variant(v: &doc::VariantDoc) -> json::Json {
+    let mut obj = ~TreeMap::new();
+    obj.insert(~"name", json::String(copy v.name));
+    obj.insert(~"desc", str_or_null(&v.desc));
+    obj.insert(~"sig", str_or_null(&v.sig));
+    json::Object(obj)
+}
+
+fn method(m: &doc::MethodDoc) -> json::Json {
+    let mut obj = ~TreeMap::new();
+    obj.insert(~"name", json::String(copy m.name));
+    obj.insert(~"brief", str_or_null(&m.brief));
+    obj.insert(~"desc", str_or_null(&m.desc));
+    obj.insert(~"sections",
+               json::List(m.sections.iter().map(section).collect()));
+    obj.insert(~"sig", str_or_null(&m.sig));
+    json::Object(obj)
+}
+
+fn item(tag: &doc::ItemTag) -> json::Json {
+    let mut obj = item_object(tag);
+    match *tag {
+      doc::ModTag(ref doc) => {
+        obj.insert(~"items",
+                   json::List(doc.items.iter().map(item).collect()));
+      }
+      doc::NmodTag(ref doc) => {
+        obj.insert(~"fns",
+                   json::List(doc.fns.iter().map(|f| item(&doc::FnTag(copy *f)))
+                                            .collect()));
+      }
+      doc::ConstTag(ref doc) | doc::FnTag(ref doc) | doc::TyTag(ref doc) => {
+        obj.insert(~"sig", str_or_null(&doc.sig));
+        obj.insert(~"generics", generics(&doc.generics));
+      }
+      doc::EnumTag(ref doc) => {
+        obj.insert(~"variants",
+                   json::List(doc.variants.iter().map(variant).collect()));
+        obj.insert(~"generics", generics(&doc.generics));
+      }
+      doc::TraitTag(ref doc) => {
+        obj.insert(~"methods",
+                   json::List(doc.methods.iter().map(method).collect()));
+        obj.insert(~"generics", generics(&doc.generics));
+      }
+      doc::ImplTag(ref doc) => {
+        obj.insert(~"where_bounds",
+                   json::List(doc.where_bounds.iter().map(ty_param).collect()));
+        obj.insert(~"trait_types", strs(doc.trait_types));
+        obj.insert(~"self_ty", str_or_null(&doc.self_ty));
+        obj.insert(~"methods",
+                   json::List(doc.methods.iter().map(method).collect()));
+        obj.insert(~"generics", generics(&doc.generics));
+      }
+      doc::StructTag(ref doc) => {
+        obj.insert(~"fields", strs(doc.fields));
+        obj.insert(~"sig", str_or_null(&doc.sig));
+        obj.insert(~"generics", generics(&doc.generics));
+      }
+      // A stripped node is emitted as a bare stub: a later pass can turn it
+      // into a redirect, but it carries no children of its own.
+      doc::StrippedTag(*) => {
+        obj.insert(~"stripped", json::Boolean(true));
+      }
+    }
+    json::Object(obj)
+}
+
+/// Serialize a whole `Doc` tree to a versioned JSON object.
+pub fn to_json(doc: &doc::Doc) -> json::Json {
+    let mut root = ~TreeMap::new();
+    root.insert(~"version", json::Number(doc::FORMAT_VERSION as f64));
+    root.insert(~"crate", item(&doc::ModTag(copy doc.cratemod())));
+    json::Object(root)
+}
+
+#[cfg(test)]
+mod test {
+    use super::to_json;
+    use doc;
+    use extra::json;
+
+    fn mk_item(name: ~str) -> doc::ItemDoc {
+        doc::ItemDoc {
+            id: 0,
+            name: name,
+            path: ~[],
+            brief: None,
+            desc: None,
+            sections: ~[],
+            reexport: false,
+            visibility: doc::Public
+        }
+    }
+
+    // A crate with one function, stripped, to exercise the stub shape.
+    fn mk_doc() -> doc::Doc {
+        let f = doc::FnTag(doc::SimpleItemDoc {
+            item: mk_item(~"foo"),
+            sig: Some(~"fn foo()"),
+            generics: None
+        });
+        let topmod = doc::ModDoc {
+            item: mk_item(~"crate"),
+            items: ~[f.strip()],
+            index: None
+        };
+        doc::Doc { pages: ~[doc::CratePage(doc::CrateDoc { topmod: topmod })] }
+    }
+
+    fn field<'a>(obj: &'a json::Json, key: &str) -> &'a json::Json {
+        match *obj {
+          json::Object(ref map) => map.find(&key.to_str()).unwrap(),
+          _ => fail!("not an object")
+        }
+    }
+
+    #[test]
+    fn emits_format_version() {
+        let out = to_json(&mk_doc());
+        assert_eq!(*field(&out, "version"),
+                   json::Number(doc::FORMAT_VERSION as f64));
+    }
+
+    #[test]
+    fn tags_items_by_kind() {
+        let out = to_json(&mk_doc());
+        assert_eq!(*field(field(&out, "crate"), "kind"), json::String(~"mod"));
+    }
+
+    #[test]
+    fn stripped_item_is_a_stub() {
+        let out = to_json(&mk_doc());
+        let items = match *field(field(&out, "crate"), "items") {
+          json::List(ref l) => copy *l,
+          _ => fail!("items not a list")
+        };
+        assert_eq!(items.len(), 1);
+        // The stub keeps the inner kind but carries no `sig`/children.
+        assert_eq!(*field(&items[0], "kind"), json::String(~"fn"));
+        assert_eq!(*field(&items[0], "stripped"), json::Boolean(true));
+    }
+}